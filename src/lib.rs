@@ -1,22 +1,29 @@
 #[macro_use]
 extern crate slog;
 use std::{
+    collections::BTreeMap,
     error::Error,
-    net::{IpAddr, ToSocketAddrs},
+    fs,
+    net::{IpAddr, Ipv6Addr, ToSocketAddrs},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
     thread,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use ansi_term::Color;
+use arc_swap::ArcSwap;
+use byteorder::{ByteOrder, NetworkEndian};
 use hsl::HSL;
 use pnet::{
     packet::{
         icmp::{echo_reply, echo_request, IcmpTypes},
-        icmpv6::{Icmpv6Types, MutableIcmpv6Packet},
+        icmpv6::{
+            checksum as icmpv6_checksum, echo_reply as icmpv6_echo_reply,
+            echo_request as icmpv6_echo_request, Icmpv6Packet, Icmpv6Types,
+        },
         ip::IpNextHeaderProtocols,
         Packet,
     },
@@ -29,25 +36,23 @@ use pnet::{
 };
 use rand::random;
 
-/// configs passed to the run function
-pub struct Config
+/// the tunable probe parameters that can be hot-reloaded at runtime
+///
+/// these are kept separate from the immutable parts of `Config` so that a whole
+/// validated snapshot can be swapped atomically in response to `SIGHUP`
+#[derive(Clone)]
+pub struct Settings
 {
-    logger: slog::Logger,
-    destination: IpAddr,
     count: u16,
     interval: f64,
     timeout: f64,
 }
 
-impl Config
+impl Settings
 {
-    pub fn new(
-        logger: slog::Logger,
-        destination: String,
-        count: u16,
-        mut interval: f64,
-        timeout: f64,
-    ) -> Option<Config>
+    /// validate raw tunables into a `Settings` snapshot, applying the minimum
+    /// interval floor for unprivileged users and rejecting negative values
+    fn new(logger: &slog::Logger, count: u16, mut interval: f64, timeout: f64) -> Option<Settings>
     {
         if interval < 0.0 {
             crit!(logger, "the value of 'interval' cannot be negative");
@@ -71,24 +76,126 @@ impl Config
             interval = 0.2
         }
 
-        // resolve destination String into IpAddr
-        let destination = match Config::resolve_hostname(destination) {
-            Ok(destination) => destination,
-            Err(error) => {
-                crit!(logger, "{}", error);
-                return None;
+        Some(Settings {
+            count,
+            interval,
+            timeout,
+        })
+    }
+}
+
+/// configs passed to the run function
+pub struct Config
+{
+    logger: slog::Logger,
+    destinations: Vec<IpAddr>,
+    config_path: Option<String>,
+    settings: Arc<ArcSwap<Settings>>,
+}
+
+impl Config
+{
+    pub fn new(
+        logger: slog::Logger,
+        hostnames: Vec<String>,
+        config_path: Option<String>,
+        count: u16,
+        interval: f64,
+        timeout: f64,
+    ) -> Option<Config>
+    {
+        // validate the initial tunables up front
+        let settings = Settings::new(&logger, count, interval, timeout)?;
+
+        // resolve each destination String into an IpAddr
+        let mut destinations: Vec<IpAddr> = Vec::new();
+        for hostname in hostnames {
+            match Config::resolve_hostname(hostname) {
+                Ok(destination) => destinations.push(destination),
+                Err(error) => {
+                    crit!(logger, "{}", error);
+                    return None;
+                }
             }
-        };
+        }
 
         Some(Config {
             logger,
-            destination,
-            count,
-            interval,
-            timeout,
+            destinations,
+            config_path,
+            settings: Arc::new(ArcSwap::from_pointee(settings)),
         })
     }
 
+    /// re-read the tunable parameters from the configuration file and atomically
+    /// store a new snapshot; if the file cannot be read or the resulting values
+    /// fail validation the current snapshot is kept unchanged
+    ///
+    /// the file holds `key = value` lines for `count`, `interval` and `timeout`;
+    /// omitted keys and keys whose value does not parse keep the currently active
+    /// value, while the remaining keys are still applied
+    fn reload_settings(&self)
+    {
+        let path = match &self.config_path {
+            Some(path) => path,
+            None => {
+                warn!(self.logger, "received SIGHUP but no config file is set");
+                return;
+            }
+        };
+
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                warn!(self.logger, "unable to read config file '{}': {}", path, error);
+                return;
+            }
+        };
+
+        // start from the currently active values so partial files are allowed
+        let current = self.settings.load();
+        let mut count = current.count;
+        let mut interval = current.interval;
+        let mut timeout = current.timeout;
+
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            match key {
+                "count" => {
+                    if let Ok(parsed) = value.parse::<u16>() {
+                        count = parsed
+                    }
+                }
+                "interval" => {
+                    if let Ok(parsed) = value.parse::<f64>() {
+                        interval = parsed
+                    }
+                }
+                "timeout" => {
+                    if let Ok(parsed) = value.parse::<f64>() {
+                        timeout = parsed
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // only swap in the new snapshot if it passes validation
+        if let Some(settings) = Settings::new(&self.logger, count, interval, timeout) {
+            self.settings.store(Arc::new(settings));
+            info!(self.logger, "reloaded configuration from '{}'", path);
+        }
+    }
+
     /// resolve hostname String into IpAddr
     ///
     /// # Arguments
@@ -131,6 +238,145 @@ impl Config
     }
 }
 
+/// per-destination probe state and accumulated statistics
+///
+/// every host carries its own sequence counter and waiting queue so that a
+/// shared identifier plus the reply's source address is enough to attribute
+/// each answer to the correct target
+struct HostStats
+{
+    address: IpAddr,
+    sequence: u16,
+    transmitted: u16,
+    received: u16,
+    total_rtt: Duration,
+    sum_sq: f64,
+    min: Option<Duration>,
+    max: Option<Duration>,
+    waiting_queue: BTreeMap<u16, Instant>,
+}
+
+impl HostStats
+{
+    /// create an empty statistics block for `address`
+    fn new(address: IpAddr) -> HostStats
+    {
+        HostStats {
+            address,
+            sequence: 0,
+            transmitted: 0,
+            received: 0,
+            total_rtt: Duration::new(0, 0),
+            sum_sq: 0.0,
+            min: None,
+            max: None,
+            waiting_queue: BTreeMap::new(),
+        }
+    }
+
+    /// fold a received RTT sample into the running accumulators
+    fn record(&mut self, rtt: Duration)
+    {
+        // if min is not initialized, set min=rtt
+        // else compare and set accordingly
+        if let Some(current_min) = self.min {
+            if rtt < current_min {
+                self.min = Some(rtt)
+            }
+        }
+        else {
+            self.min = Some(rtt)
+        }
+
+        // if max is not initialized, set max=rtt
+        // else compare and set accordingly
+        if let Some(current_max) = self.max {
+            if rtt > current_max {
+                self.max = Some(rtt)
+            }
+        }
+        else {
+            self.max = Some(rtt)
+        }
+
+        self.total_rtt += rtt;
+        self.sum_sq += (rtt.as_micros() as f64).powi(2);
+        self.received += 1;
+    }
+
+    /// print the final statistics block for this host
+    fn print_summary(&self, logger: &slog::Logger)
+    {
+        info!(
+            logger,
+            "{}",
+            Color::Fixed(240)
+                .bold()
+                .paint(format!("{} ping statistics", self.address))
+        );
+
+        // calculate %loss
+        let loss = if self.transmitted == 0 {
+            100.0
+        }
+        else {
+            ((self.transmitted - self.received) as f64 / self.transmitted as f64) * 100.0
+        };
+
+        info!(
+            logger,
+            "{}",
+            Color::Fixed(240).bold().paint(format!(
+                "transmitted={} received={} loss={:.4}%",
+                self.transmitted, self.received, loss
+            ))
+        );
+
+        let final_min = match self.min {
+            None => Duration::new(0, 0),
+            Some(min) => min,
+        };
+
+        let final_max = match self.max {
+            None => Duration::new(0, 0),
+            Some(max) => max,
+        };
+
+        let avg = if self.received == 0 {
+            0
+        }
+        else {
+            self.total_rtt.as_micros() / self.received as u128
+        };
+
+        // mean deviation (jitter): sqrt(E[x^2] - E[x]^2), clamped to zero to guard
+        // against a tiny negative variance produced by floating point rounding
+        let mdev = if self.received == 0 {
+            0
+        }
+        else {
+            let n = self.received as f64;
+            let mean = self.total_rtt.as_micros() as f64 / n;
+            let variance = (self.sum_sq / n - mean * mean).max(0.0);
+            variance.sqrt() as u128
+        };
+
+        info!(
+            logger,
+            "{}{}{}{}{}{}{}{}{}",
+            Color::Fixed(240).bold().paint("min="),
+            paint_rtt(final_min.as_micros()),
+            Color::Fixed(240).bold().paint("ms max="),
+            paint_rtt(final_max.as_micros()),
+            Color::Fixed(240).bold().paint("ms avg="),
+            paint_rtt(avg),
+            Color::Fixed(240).bold().paint("ms mdev="),
+            paint_rtt(mdev),
+            Color::Fixed(240).bold().paint("ms")
+        );
+    }
+}
+
 /// use ansi_term to color the rtt value and returns
 /// the colored value as a string
 ///
@@ -169,44 +415,97 @@ fn paint_rtt(rtt: u128) -> String
     }
 }
 
-/// send ICMP/ICMPv6 echo request to an address and return the RTT if a response is received
-/// if no responses are received, return Ok(None)
+/// number of leading payload bytes reserved for the big-endian send timestamp
+const TIMESTAMP_LEN: usize = 8;
+
+/// current wall-clock time in microseconds since the Unix epoch
+///
+/// used as a compact, self-describing timestamp that can be round-tripped
+/// through the ICMP payload, so RTT is taken from the echoed data rather than
+/// from a locally stored send time
+fn now_micros() -> u64
+{
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as u64
+}
+
+/// build an echo payload of `size` header-exclusive bytes: the send timestamp
+/// in the first `TIMESTAMP_LEN` bytes followed by a deterministic byte pattern
+fn echo_payload(size: usize) -> Vec<u8>
+{
+    let mut payload: Vec<u8> = vec![0; size];
+
+    // write the transmit time so RTT can be recovered from the echoed data
+    if size >= TIMESTAMP_LEN {
+        NetworkEndian::write_u64(&mut payload[..TIMESTAMP_LEN], now_micros());
+    }
+
+    // fill the remainder with a known pattern to detect corrupted replies
+    for (index, byte) in payload.iter_mut().enumerate().skip(TIMESTAMP_LEN) {
+        *byte = index as u8;
+    }
+
+    payload
+}
+
+/// validate an echoed payload against the pattern written by `echo_payload` and,
+/// if it matches, return the embedded transmit timestamp in microseconds
+fn validate_payload(payload: &[u8]) -> Option<u64>
+{
+    if payload.len() < TIMESTAMP_LEN {
+        return None;
+    }
+
+    // a mismatch means the data was corrupted or the reply is not ours
+    for (index, &byte) in payload.iter().enumerate().skip(TIMESTAMP_LEN) {
+        if byte != index as u8 {
+            return None;
+        }
+    }
+
+    Some(NetworkEndian::read_u64(&payload[..TIMESTAMP_LEN]))
+}
+
+/// build and send a single ICMP/ICMPv6 echo request on an existing transport channel
+///
+/// unlike a blocking ping, this only emits the request; matching the reply back up
+/// with its send time is the job of the waiting queue drained by `poll_replies`
 ///
 /// # Arguments
 ///
+/// * `sender` - the long-lived transport sender to write the packet to
 /// * `address` - IPv4 or IPv6 address to ping
-/// * `timeout` - ICMP echo receival timeout
 /// * `size` - ICMP echo data size
 /// * `sequence` - ICMP echo sequence number
 /// * `identifier` - ICMP echo identifier
 ///
 /// # Errors
 ///
-/// std::io::Error if packets cannot be sent
+/// std::io::Error if the packet cannot be sent
 ///
 /// # Examples
 ///
 /// ```
-/// ping(
-///     std::net::Ipv4Addr::new(1, 1, 1, 1),
-///     time::Duration::new(1, 0),
+/// send_echo(
+///     &mut sender,
+///     std::net::Ipv4Addr::new(1, 1, 1, 1).into(),
 ///     64,
-///     rand::random::<u16>(),
+///     0,
 ///     random::<u16>(),
-/// )
+/// )?;
 /// ```
-fn ping(
+fn send_echo(
+    sender: &mut TransportSender,
     address: IpAddr,
-    timeout: f64,
     size: usize,
     sequence: u16,
     identifier: u16,
-) -> Result<Option<Duration>, std::io::Error>
+) -> Result<(), std::io::Error>
 {
     // allocate space for packet
     let mut packet_buffer: Vec<u8> = vec![0; size];
-    let mut sender: TransportSender;
-    let mut receiver: TransportReceiver;
 
     // if the target address is an IPv4 address
     if address.is_ipv4() {
@@ -215,93 +514,150 @@ fn ping(
         packet.set_icmp_type(IcmpTypes::EchoRequest);
         packet.set_sequence_number(sequence);
         packet.set_identifier(identifier);
-        packet.set_checksum(pnet::util::checksum(&packet.packet(), 1));
-        (sender, receiver) = transport_channel(size, Layer4(Ipv4(IpNextHeaderProtocols::Icmp)))?;
+        // embed the transmit timestamp plus a known pattern into the data field
+        packet.set_payload(&echo_payload(size - echo_request::MutableEchoRequestPacket::minimum_packet_size()));
+        packet.set_checksum(pnet::util::checksum(packet.packet(), 1));
         sender.send_to(packet, address)?;
 
     // if the target address is an IPv6 address
     }
     else {
-        let mut packet = MutableIcmpv6Packet::new(&mut packet_buffer[..]).unwrap();
+        let mut packet =
+            icmpv6_echo_request::MutableEchoRequestPacket::new(&mut packet_buffer[..]).unwrap();
         packet.set_icmpv6_type(Icmpv6Types::EchoRequest);
-        (sender, receiver) = transport_channel(size, Layer4(Ipv6(IpNextHeaderProtocols::Icmpv6)))?;
+        packet.set_identifier(identifier);
+        packet.set_sequence_number(sequence);
+        // embed the transmit timestamp plus a known pattern into the data field
+        packet.set_payload(&echo_payload(
+            size - icmpv6_echo_request::MutableEchoRequestPacket::minimum_packet_size(),
+        ));
+
+        // compute the ICMPv6 checksum over the IPv6 pseudo-header; the source is
+        // left unspecified as the kernel substitutes the real address and
+        // finalizes the checksum when the packet leaves the raw socket
+        let destination = match address {
+            IpAddr::V6(destination) => destination,
+            IpAddr::V4(_) => unreachable!(),
+        };
+        let checksum = {
+            let view = Icmpv6Packet::new(packet.packet()).unwrap();
+            icmpv6_checksum(&view, &Ipv6Addr::UNSPECIFIED, &destination)
+        };
+        packet.set_checksum(checksum);
+
         sender.send_to(packet, address)?;
     }
 
-    // start timer
-    let sent_time = Instant::now();
-    let mut loop_timeout = Duration::from_secs_f64(timeout);
+    Ok(())
+}
+
+/// drain every echo reply that is currently available on the receiver without
+/// blocking, attributing each one to a host by source address and sequence and
+/// returning `(host index, sequence, rtt)` tuples for the answered requests
+///
+/// matched entries are removed from the owning host's waiting queue so that the
+/// caller is left holding only the still-outstanding requests to time out
+///
+/// # Arguments
+///
+/// * `receiver` - the long-lived transport receiver to read replies from
+/// * `is_ipv4` - whether this receiver carries the ICMP (`true`) or ICMPv6 path
+/// * `identifier` - ICMP echo identifier used to filter foreign replies
+/// * `hosts` - per-destination state whose waiting queues are matched against
+fn poll_replies(
+    receiver: &mut TransportReceiver,
+    is_ipv4: bool,
+    identifier: u16,
+    hosts: &mut [HostStats],
+) -> Vec<(usize, u16, Duration)>
+{
+    let mut replies: Vec<(usize, u16, Duration)> = Vec::new();
 
     // ICMP
-    if address.is_ipv4() {
-        let mut receiver_iterator = icmp_packet_iter(&mut receiver);
-        loop {
-            // get data from receiver
-            let data = receiver_iterator.next_with_timeout(loop_timeout).unwrap();
-
-            match data {
-                None => return Ok(None),
-                Some(data) => {
-                    let (received, _address) = data;
-                    if received.get_icmp_type() == IcmpTypes::EchoReply {
-                        let reply = echo_reply::EchoReplyPacket::new(received.packet()).unwrap();
-
-                        if reply.get_identifier() == identifier
-                            && reply.get_sequence_number() == sequence
-                        {
-                            // return rtt = now - start
-                            return Ok(Some(Instant::now().duration_since(sent_time)));
-
-                        // this should not happen
-                        // we have not sent a packet with a greater sequence number yet
-                        }
-                        else if reply.get_identifier() == identifier
-                            && reply.get_sequence_number() >= sequence
-                        {
-                            panic!("got impossible sequence number")
-                        }
-                    }
-                }
+    if is_ipv4 {
+        let mut receiver_iterator = icmp_packet_iter(receiver);
+
+        // a zero timeout makes next_with_timeout return immediately once the
+        // socket has no more packets ready, giving us a non-blocking drain
+        while let Ok(Some((received, source))) =
+            receiver_iterator.next_with_timeout(Duration::from_millis(0))
+        {
+            if received.get_icmp_type() != IcmpTypes::EchoReply {
+                continue;
             }
 
-            // if the amount of time elapsed has yet exceeded the specified timeout
-            // set (timeout = timeout - elapsed time) and listen for another packet
-            if Instant::now().duration_since(sent_time) > Duration::from_secs_f64(timeout) {
-                return Ok(None);
+            let reply = echo_reply::EchoReplyPacket::new(received.packet()).unwrap();
+
+            // ignore replies that belong to a different pinger
+            if reply.get_identifier() != identifier {
+                continue;
             }
-            else {
-                loop_timeout =
-                    Duration::from_secs_f64(timeout) - Instant::now().duration_since(sent_time)
+
+            // attribute the reply to the host it came from
+            let index = match hosts.iter().position(|host| host.address == source) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            // discard replies whose echoed data does not match what we sent,
+            // which also yields the transmit timestamp embedded in the payload
+            let timestamp = match validate_payload(reply.payload()) {
+                Some(timestamp) => timestamp,
+                None => continue,
+            };
+
+            // attribute the reply to its outstanding request by sequence number;
+            // RTT runs from the echoed send timestamp to the moment the reply is
+            // read here, so time a reply spends waiting for a later drain is included
+            let sequence = reply.get_sequence_number();
+            if hosts[index].waiting_queue.remove(&sequence).is_some() {
+                let rtt = Duration::from_micros(now_micros().saturating_sub(timestamp));
+                replies.push((index, sequence, rtt));
             }
         }
 
     // ICMPv6
     }
     else {
-        let mut receiver_iterator = icmpv6_packet_iter(&mut receiver);
-        loop {
-            // get data from receiver
-            let data = receiver_iterator.next_with_timeout(loop_timeout).unwrap();
-
-            match data {
-                None => return Ok(None),
-                Some(data) => {
-                    let (received, _address) = data;
-                    if received.get_icmpv6_type() == Icmpv6Types::EchoReply {
-                        return Ok(Some(Instant::now().duration_since(sent_time)));
-                    }
-                }
+        let mut receiver_iterator = icmpv6_packet_iter(receiver);
+
+        while let Ok(Some((received, source))) =
+            receiver_iterator.next_with_timeout(Duration::from_millis(0))
+        {
+            if received.get_icmpv6_type() != Icmpv6Types::EchoReply {
+                continue;
             }
 
-            if Instant::now().duration_since(sent_time) > Duration::from_secs_f64(timeout) {
-                return Ok(None);
+            let reply = icmpv6_echo_reply::EchoReplyPacket::new(received.packet()).unwrap();
+
+            // ignore replies that belong to a different pinger
+            if reply.get_identifier() != identifier {
+                continue;
             }
-            else {
-                loop_timeout =
-                    Duration::from_secs_f64(timeout) - Instant::now().duration_since(sent_time)
+
+            // attribute the reply to the host it came from
+            let index = match hosts.iter().position(|host| host.address == source) {
+                Some(index) => index,
+                None => continue,
+            };
+
+            // discard replies whose echoed data does not match what we sent,
+            // which also yields the transmit timestamp embedded in the payload
+            let timestamp = match validate_payload(reply.payload()) {
+                Some(timestamp) => timestamp,
+                None => continue,
+            };
+
+            // attribute the reply to its outstanding request by sequence number
+            let sequence = reply.get_sequence_number();
+            if hosts[index].waiting_queue.remove(&sequence).is_some() {
+                let rtt = Duration::from_micros(now_micros().saturating_sub(timestamp));
+                replies.push((index, sequence, rtt));
             }
         }
     }
+
+    replies
 }
 
 /// send ping requests in a loop and print the stats
@@ -323,7 +679,8 @@ fn ping(
 ///         let drain = Mutex::new(slog_term::FullFormat::new(decorator).build()).fuse();
 ///         slog::Logger::root(drain, o!())
 ///     },
-///     "1.1.1.1",
+///     vec!["1.1.1.1".to_string()],
+///     None,
 ///     4_u16,
 ///     1.0_f64,
 ///     1.0_f64,
@@ -331,14 +688,13 @@ fn ping(
 /// ```
 pub fn run(config: Config) -> Result<(), Box<dyn Error>>
 {
-    // declare/initialize internal metric variables for the ping summary
+    // a single identifier is shared across every target; replies are told apart
+    // by their source address and per-host sequence number
     let identifier = random::<u16>();
-    let mut sequence: u16 = 0;
-    let mut total_rtt = Duration::new(0, 0);
-    let mut transmitted = 0;
-    let mut received = 0;
-    let mut min: Option<Duration> = None;
-    let mut max: Option<Duration> = None;
+
+    // per-destination probe state and accumulated statistics
+    let mut hosts: Vec<HostStats> =
+        config.destinations.iter().map(|&address| HostStats::new(address)).collect();
 
     // an atomic boolean value that acts as the running flag
     // this is used to stop the ping cycle when ^C is pressed
@@ -351,132 +707,130 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error>>
     })
     .expect("error setting Ctrl-C handler");
 
-    // keep sending pings until ^C is pressed or count is reached
-    while running.load(Ordering::SeqCst) && (config.count == 0 || sequence < config.count) {
+    // a flag raised by the SIGHUP handler; the main loop drains it each cycle and
+    // reloads the tunable settings so `kill -HUP` takes effect without a restart
+    let reload = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, reload.clone())
+        .expect("error setting SIGHUP handler");
+
+    // create one long-lived transport channel per address family in use instead
+    // of reallocating a socket for every packet
+    let has_ipv4 = hosts.iter().any(|host| host.address.is_ipv4());
+    let has_ipv6 = hosts.iter().any(|host| host.address.is_ipv6());
+    let mut ipv4_channel = if has_ipv4 {
+        Some(transport_channel(2048, Layer4(Ipv4(IpNextHeaderProtocols::Icmp)))?)
+    }
+    else {
+        None
+    };
+    let mut ipv6_channel = if has_ipv6 {
+        Some(transport_channel(2048, Layer4(Ipv6(IpNextHeaderProtocols::Icmpv6)))?)
+    }
+    else {
+        None
+    };
+
+    // keep cycling until ^C is pressed or every host has reached the count and
+    // drained its outstanding requests; the count is read from the live snapshot
+    while running.load(Ordering::SeqCst)
+        && hosts.iter().any(|host| {
+            let count = config.settings.load().count;
+            count == 0 || host.transmitted < count || !host.waiting_queue.is_empty()
+        })
+    {
         // this timer is used to calculate interval
         let cycle_begin_time = Instant::now();
 
-        // send one echo request and get the RTT value
-        let rtt = match ping(config.destination, config.timeout, 64, sequence, identifier) {
-            Ok(rtt) => rtt,
-            Err(error) => {
-                crit!(config.logger, "{}", error);
-                return Err(error.into());
+        // if a SIGHUP arrived since the last cycle, atomically reload the tunables
+        if reload.swap(false, Ordering::SeqCst) {
+            config.reload_settings();
+        }
+
+        // load the current snapshot once and use it for the whole cycle
+        let settings = config.settings.load();
+        let timeout = Duration::from_secs_f64(settings.timeout);
+
+        // emit one echo request per host per cycle until the count is reached
+        for host in hosts.iter_mut() {
+            if !running.load(Ordering::SeqCst) {
+                break;
+            }
+            if settings.count != 0 && host.transmitted >= settings.count {
+                continue;
             }
-        };
 
-        match rtt {
-            None => {
-                warn!(
-                    config.logger,
-                    "no answer from {} seq={}", config.destination, sequence
-                );
+            let sender = if host.address.is_ipv4() {
+                &mut ipv4_channel.as_mut().unwrap().0
             }
-            Some(rtt) => {
-                // if min is not initialized, set min=millis
-                // else compare and set accordingly
-                if let Some(current_min) = min {
-                    if rtt < current_min {
-                        min = Some(rtt)
-                    }
-                }
-                else {
-                    min = Some(rtt)
-                }
+            else {
+                &mut ipv6_channel.as_mut().unwrap().0
+            };
 
-                // if max is not initialized, set max=millis
-                // else compare and set accordingly
-                if let Some(current_max) = max {
-                    if rtt > current_max {
-                        max = Some(rtt)
-                    }
-                }
-                else {
-                    max = Some(rtt)
-                }
+            if let Err(error) = send_echo(sender, host.address, 64, host.sequence, identifier) {
+                crit!(config.logger, "{}", error);
+                return Err(error.into());
+            }
+            host.waiting_queue.insert(host.sequence, Instant::now());
+            host.transmitted += 1;
+            host.sequence += 1;
+        }
 
-                info!(
-                    config.logger,
-                    "answer from {} seq={} rtt={}ms",
-                    config.destination,
-                    sequence,
-                    paint_rtt(rtt.as_micros())
-                );
+        // drain any replies that have arrived since the last cycle, routing each
+        // to the host it belongs to
+        let mut replies: Vec<(usize, u16, Duration)> = Vec::new();
+        if let Some((_, ref mut receiver)) = ipv4_channel {
+            replies.extend(poll_replies(receiver, true, identifier, &mut hosts));
+        }
+        if let Some((_, ref mut receiver)) = ipv6_channel {
+            replies.extend(poll_replies(receiver, false, identifier, &mut hosts));
+        }
 
-                total_rtt += rtt;
-                received += 1;
+        for (index, seq, rtt) in replies {
+            let host = &mut hosts[index];
+            info!(
+                config.logger,
+                "answer from {} seq={} rtt={}ms",
+                host.address,
+                seq,
+                paint_rtt(rtt.as_micros())
+            );
+            host.record(rtt);
+        }
+
+        // expire any request that has been waiting longer than the timeout
+        let now = Instant::now();
+        for host in hosts.iter_mut() {
+            let expired: Vec<u16> = host
+                .waiting_queue
+                .iter()
+                .filter(|(_, &sent_time)| now.duration_since(sent_time) > timeout)
+                .map(|(&seq, _)| seq)
+                .collect();
+            for seq in expired {
+                host.waiting_queue.remove(&seq);
+                warn!(config.logger, "no answer from {} seq={}", host.address, seq);
             }
         }
-        transmitted += 1;
-        sequence += 1;
 
         // if current time - elapsed time < interval, wait until interval is reached
         if Instant::now().duration_since(cycle_begin_time)
-            < Duration::from_secs_f64(config.interval)
+            < Duration::from_secs_f64(settings.interval)
         {
             thread::sleep(
-                Duration::from_secs_f64(config.interval)
+                Duration::from_secs_f64(settings.interval)
                     - Instant::now().duration_since(cycle_begin_time),
             )
         }
     }
 
-    // print final statistics
-    info!(
-        config.logger,
-        "{}",
-        Color::Fixed(240)
-            .bold()
-            .paint(format!("{} ping statistics", config.destination))
-    );
-
-    // calculate %loss
-    let loss = if transmitted == 0 {
-        100.0
-    }
-    else {
-        ((transmitted - received) as f64 / transmitted as f64) * 100.0
-    };
-
-    info!(
-        config.logger,
-        "{}",
-        Color::Fixed(240).bold().paint(format!(
-            "transmitted={} received={} loss={:.4}%",
-            transmitted, received, loss
-        ))
-    );
-
-    let final_min = match min {
-        None => Duration::new(0, 0),
-        Some(min) => min,
-    };
-
-    let final_max = match max {
-        None => Duration::new(0, 0),
-        Some(max) => max,
-    };
-
-    let avg = if sequence == 0 {
-        0
+    // print an independent statistics block per target
+    for host in hosts.iter() {
+        host.print_summary(&config.logger);
     }
-    else {
-        total_rtt.as_micros() / sequence as u128
-    };
 
-    info!(
-        config.logger,
-        "{}{}{}{}{}{}{}",
-        Color::Fixed(240).bold().paint("min="),
-        paint_rtt(final_min.as_micros()),
-        Color::Fixed(240).bold().paint("ms max="),
-        paint_rtt(final_max.as_micros()),
-        Color::Fixed(240).bold().paint("ms avg="),
-        paint_rtt(avg),
-        Color::Fixed(240).bold().paint("ms")
-    );
-
-    // return an error if no successful responses were received
+    // return an error if packets were sent but no host ever answered
+    let transmitted: u32 = hosts.iter().map(|host| host.transmitted as u32).sum();
+    let received: u32 = hosts.iter().map(|host| host.received as u32).sum();
     if transmitted > 0 && received == 0 {
         return Err("no responses have been received".into());
     }
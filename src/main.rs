@@ -11,9 +11,13 @@ use slog::Drain;
 #[command(author, version, about, long_about = None)]
 struct Args
 {
-    /// dns name or ip address
-    #[arg(index = 1)]
-    destination: String,
+    /// dns name(s) or ip address(es)
+    #[arg(index = 1, num_args = 1.., required = true)]
+    destination: Vec<String>,
+
+    /// path to a config file re-read on SIGHUP
+    #[arg(short = 'f', long)]
+    config: Option<String>,
 
     /// stop after <count> replies
     #[arg(short = 'c', long, default_value_t = 0)]
@@ -45,6 +49,7 @@ fn parse() -> Option<Config>
             slog::Logger::root(drain, o!())
         },
         args.destination,
+        args.config,
         args.count,
         args.interval,
         args.timeout,